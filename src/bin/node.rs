@@ -4,8 +4,8 @@ use futures::stream::StreamExt;
 use std::error::Error;
 
 use tokio::{
-    io, 
-    io::AsyncBufReadExt, 
+    io,
+    io::AsyncBufReadExt,
     select,
     sync::mpsc
 };
@@ -13,20 +13,26 @@ use tokio::{
 use tracing_subscriber::EnvFilter;
 
 use ishishnet::{
-    consensus::IshIshBlockchain,
+    consensus::DvbBlockchain,
     config::Config,
     common::{
-        ensure_ishish_home,
+        ensure_dvb_home,
         DEFAULT_DIFFICULTY
     },
     data::build_swarm,
-    settlement::IshIshTransaction
+    settlement::{
+        DvbTransaction,
+        load_state_from_store
+    },
+    engine::{AuthorityRoundEngine, BlockSource, ConsensusKind},
+    rpc::run_rpc_server,
+    spec::ChainSpec
 };
 
 use revm::{
     db::{
-        CacheDB, 
-        EmptyDB, 
+        CacheDB,
+        EmptyDB,
     },
     Evm,
 };
@@ -36,20 +42,21 @@ use ishishnet::consensus::{
     mining_task
 };
 use ishishnet::data::process_event;
+use ishishnet::rpc::process_rpc_request;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
 
-    
+
     let _ = tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::from_default_env())
         .try_init();
 
-    ensure_ishish_home().await?;
+    let dvb_home = ensure_dvb_home().await?;
 
     /* Setting up the data availability layer */
     let (
-        mut swarm, 
+        mut swarm,
         topic
     ) = build_swarm()?;
 
@@ -62,34 +69,95 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     /* Local representation of the blockchain */
 
-    /* Local state */
-    let my_state = CacheDB::new(EmptyDB::default());
+    /* A chain spec is opt-in via DVB_CHAIN_SPEC: it lets a reproducible
+     * scenario (pre-funded accounts, a fixed genesis, a chosen engine) ship
+     * as a single file instead of relying on hardcoded defaults. */
+    let chain_spec = match std::env::var("DVB_CHAIN_SPEC") {
+        Ok(path) => Some(ChainSpec::load(path)?),
+        Err(_) => None,
+    };
+    let consensus = chain_spec.as_ref().map(|spec| spec.engine.kind).unwrap_or(ConsensusKind::ProofOfWork);
+
+    /* Open the persisted block/balance store, rebuild the chain from it under
+     * the selected engine, and materialize state from it, so a restarted node
+     * resumes where it left off instead of starting from genesis. */
+    let mut db_path = dvb_home.clone();
+    db_path.push("blockchain.db");
+    let (mut my_blockchain, store) = DvbBlockchain::open(db_path, consensus)?;
+
+    /* Local state, pre-seeded from the chain spec's `accounts` if one is given */
+    let mut my_state = match &chain_spec {
+        Some(spec) => spec.build_state(),
+        None => CacheDB::new(EmptyDB::default()),
+    };
+    load_state_from_store(&mut my_state, &store)?;
 
     /* Local EVM */
     let my_evm = Evm::builder().with_db(my_state).build();
 
     /* Local transaction pool */
-    let my_transactions: Vec<IshIshTransaction> = Vec::new();
+    let my_transactions: Vec<DvbTransaction> = Vec::new();
+
+    /* If nothing has been persisted yet and a chain spec was given, seed the
+     * chain with the spec's genesis block instead of waiting on the mining
+     * task. */
+    if my_blockchain.blocks.is_empty() {
+        if let Some(spec) = &chain_spec {
+            let genesis = spec.genesis_block();
+            /* Genesis has no parent, so `authority_round` is never consulted
+             * for it regardless of engine. */
+            my_blockchain.append(genesis.clone(), consensus, BlockSource::Network, None)?;
+            store.add_block(&my_blockchain, &genesis, consensus, None)?;
+        }
+    }
 
-    /* Local blockchain */
-    let my_blockchain = IshIshBlockchain::new();
 
-    
     /* Prepare local mining task */
-    
+
     /* Channels for commands and blocks */
     let (command_tx, command_rx) = mpsc::channel(10);
     let (block_tx, block_rx) = mpsc::channel(10);
 
-    /* Set the difficulty */
-    let difficulty: usize = match std::env::args().nth(1)
-    {
-        Some(v) => v.parse::<usize>().unwrap(),
-        None => DEFAULT_DIFFICULTY as usize
+    /* Set the difficulty: from the chain spec's engine section if one was
+     * given, otherwise the CLI arg this node always supported */
+    let difficulty: usize = match &chain_spec {
+        Some(spec) => spec.engine.difficulty,
+        None => match std::env::args().nth(1) {
+            Some(v) => v.parse::<usize>().unwrap(),
+            None => DEFAULT_DIFFICULTY as usize
+        },
     };
 
+    /* Only meaningful under AuthorityRound: the validator set and step clock
+     * `mining_task` needs to decide whose turn it is. No signer is attached
+     * here -- `validator_signature` is async/wallet-bound, so it's signed by
+     * `process_block` instead, once a wallet is open. */
+    let authority_round = chain_spec.as_ref().and_then(|spec| {
+        if spec.engine.kind == ConsensusKind::AuthorityRound {
+            Some(AuthorityRoundEngine {
+                validators: spec.engine.validators.clone(),
+                step_duration: spec.engine.step_duration,
+                signer: None,
+            })
+        } else {
+            None
+        }
+    });
+
     println!("Starting the local mining task");
-    tokio::spawn(mining_task(command_rx, block_tx));
+    tokio::spawn(mining_task(command_rx, block_tx, consensus, authority_round.clone()));
+
+    /* JSON-RPC is opt-in: only bind it if DVB_RPC_ADDR is set. The server
+     * handle is kept alive for the lifetime of the process; dropping it
+     * shuts the server down. */
+    let (rpc_tx, rpc_rx) = mpsc::channel(10);
+    let _rpc_handle = match std::env::var("DVB_RPC_ADDR") {
+        Ok(addr) => {
+            let addr = addr.parse()?;
+            Some(run_rpc_server(addr, rpc_tx).await?)
+        },
+        Err(_) => None,
+    };
 
     let mut cfg = Config {
         difficulty: difficulty,
@@ -101,6 +169,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
         block_rx: block_rx,
         swarm: swarm,
         topic: topic,
+        store: Some(store),
+        consensus: consensus,
+        authority_round: authority_round,
+        rpc_rx: Some(rpc_rx),
+        trace_execution: std::env::var("DVB_TRACE_EXECUTION").is_ok(),
     };
 
     // Kick it off
@@ -119,6 +192,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
             event = cfg.swarm.select_next_some() =>  {
                 /* Here we process the events from the data availability layer */
                 process_event(event, &mut cfg).await?;
+            },
+            Some(rpc_request) = async {
+                match cfg.rpc_rx.as_mut() {
+                    Some(rpc_rx) => rpc_rx.recv().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                /* Here we process requests coming in over the JSON-RPC server, if any */
+                process_rpc_request(rpc_request, &mut cfg).await?;
             }
         }
     }
@@ -32,10 +32,7 @@ enum Commands {
 
 use std::path::PathBuf;
 
-use ishishnet::common::{
-    ensure_ishish_home,
-    ISHISH_HOME
-};
+use ishishnet::common::ensure_dvb_home;
 
 async fn create_new_wallet(
     path: &PathBuf,
@@ -56,9 +53,7 @@ async fn create_new_wallet(
 async fn main() -> Result<(), Box<dyn Error>> {
 
     /* setup wallet dir path */
-    let mut path = ensure_ishish_home().await?;
-    
-    path.push(ISHISH_HOME);
+    let path = ensure_dvb_home().await?;
     println!("Home dir: {}", path.display());
 
 
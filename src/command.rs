@@ -2,14 +2,15 @@ use std::error::Error;
 
 use crate::config::Config;
 use crate::consensus::{
-    IshIshCommand,
+    DvbCommand,
     propose_block
 };
-use crate::common::ensure_ishish_home;
+use crate::common::ensure_dvb_home;
 use crate::data::broadcast_new_transaction;
 use crate::settlement::{
     get_address_balance,
-    IshIshTransaction
+    get_address_nonce,
+    DvbTransaction
 };
 
 use alloy::signers::wallet::Wallet;
@@ -31,8 +32,8 @@ async fn start_command(
             ).await?;                                
 
             /* Send the new block to the mining thread */
-            cfg.command_tx.send(IshIshCommand::MineBlock(new_block)).await?;
-            cfg.command_tx.send(IshIshCommand::Start).await?;
+            cfg.command_tx.send(DvbCommand::MineBlock(new_block)).await?;
+            cfg.command_tx.send(DvbCommand::Start).await?;
         },
         None => {
             println!("Please open a wallet first");
@@ -45,7 +46,7 @@ async fn start_command(
 async fn stop_command(
     cfg: &mut Config<'_>
 ) -> Result<(), Box<dyn Error>> {
-    cfg.command_tx.send(IshIshCommand::Stop).await?;
+    cfg.command_tx.send(DvbCommand::Stop).await?;
     Ok(())
 }
 
@@ -66,7 +67,7 @@ async fn open_command(
 
     std::io::stdin().read_line(&mut password)?;
 
-    let mut full_path = ensure_ishish_home().await?;
+    let mut full_path = ensure_dvb_home().await?;
 
     full_path.push(&wallet_name.trim());
 
@@ -171,13 +172,19 @@ async fn send_ish(
 
     println!("Sending {amount} ish from {src} to {dst}");
 
-    /* Prepare the transaction */
-    let transaction = IshIshTransaction {
-        from: src,
-        to: dst,
-        amount,
+    let signer = match &cfg.current_signer {
+        Some(signer) => signer,
+        None => {
+            println!("Please open a wallet first");
+            return Ok(());
+        }
     };
 
+    /* Prepare and sign the transaction so it can't be spent on someone else's behalf.
+     * This is a plain value transfer, so there's no calldata to attach. */
+    let nonce = get_address_nonce(cfg.evm.db_mut(), src);
+    let transaction = DvbTransaction::new_signed(src, Some(dst), amount, nonce, None, signer).await?;
+
     /* Broadcast the transaction */
     broadcast_new_transaction(
         &mut cfg.swarm, 
@@ -43,7 +43,10 @@ pub enum DvbError {
     InvalidProofOfWork,
     PrevHashMismatch,
     EmptyBlockchain,
-    RequestedBlockIsNone
+    RequestedBlockIsNone,
+    InsufficientFunds,
+    InvalidNonce,
+    InvalidDifficulty
 }
 
 impl Display for DvbError {
@@ -1,20 +1,21 @@
-
-
 use revm::{
     db::{
-        CacheDB, 
+        CacheDB,
         EmptyDB
     },
     Evm,
 };
 
-use crate::settlement::IshIshTransaction;
+use crate::settlement::DvbTransaction;
 use crate::consensus::{
-    IshIshBlockchain,
-    IshIshBlock,
-    IshIshCommand,
+    DvbBlockchain,
+    DvbBlock,
+    DvbCommand,
 };
 use crate::data::IshIshClientBehavior;
+use crate::storage::BlockStore;
+use crate::engine::{AuthorityRoundEngine, ConsensusKind};
+use crate::rpc::RpcRequest;
 
 use alloy::signers::wallet::LocalWallet;
 
@@ -25,11 +26,23 @@ use libp2p::gossipsub::IdentTopic;
 pub struct Config<'a> {
     pub difficulty: usize,
     pub evm: Evm<'a, (), CacheDB<EmptyDB>>,
-    pub transactions: Vec<IshIshTransaction>,
-    pub blockchain: IshIshBlockchain,
+    pub transactions: Vec<DvbTransaction>,
+    pub blockchain: DvbBlockchain,
     pub current_signer: Option<LocalWallet>,
-    pub command_tx: mpsc::Sender<IshIshCommand>,
-    pub block_rx: mpsc::Receiver<IshIshBlock>,
+    pub command_tx: mpsc::Sender<DvbCommand>,
+    pub block_rx: mpsc::Receiver<DvbBlock>,
     pub swarm: libp2p::Swarm<IshIshClientBehavior>,
     pub topic: IdentTopic,
+    /* Persisted chain/balance store; None means run in-memory only */
+    pub store: Option<BlockStore>,
+    /* Which ConsensusEngine this node seals and verifies blocks with */
+    pub consensus: ConsensusKind,
+    /* Only set under `ConsensusKind::AuthorityRound`: the validator set and
+     * step clock used to authenticate incoming blocks' `validator_signature`. */
+    pub authority_round: Option<AuthorityRoundEngine>,
+    /* Requests coming in from the JSON-RPC server, if one is running */
+    pub rpc_rx: Option<mpsc::Receiver<RpcRequest>>,
+    /* When set, contract calls/deployments are run with a `StepTracer`
+     * attached and the resulting trace is dumped to stdout */
+    pub trace_execution: bool,
 }
@@ -1,5 +1,8 @@
 use std::error::Error;
 use std::convert::TryInto;
+use std::cmp::Ordering;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 use serde::{Serialize, Deserialize};
 
 use sha2::{
@@ -10,7 +13,9 @@ use sha2::{
 use rand::Rng;
 use tokio::sync::mpsc;
 
-use alloy::primitives::Address;
+use alloy::primitives::{Address, Signature};
+use alloy::signers::Signer;
+use libp2p::PeerId;
 
 use crate::data::broadcast_new_blockchain;
 use crate::settlement::{
@@ -19,50 +24,122 @@ use crate::settlement::{
 };
 use crate::config::Config;
 use crate::common::DvbError;
+use crate::storage::BlockStore;
+use crate::engine::{AuthorityRoundEngine, BlockSource, ConsensusEngine, ConsensusKind, NullEngine, ProofOfWorkEngine};
 
+/* Desired seconds between blocks; the knob `retarget_difficulty` steers towards. */
+const TARGET_BLOCK_SECS: i64 = 15;
+/* A chain can never retarget below this many required leading zero bits */
+const MIN_DIFFICULTY: usize = 1;
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+/* Speed up (higher difficulty) if the last block came in faster than
+ * `TARGET_BLOCK_SECS`, slow down otherwise, moving by one required
+ * leading-zero bit per block -- each bit doubles the expected PoW work, so
+ * this is already a meaningful step at the 0..=256 scale `difficulty` lives
+ * in. An Ethash-style "1/2048th of the parent's difficulty" step would
+ * truncate to 0 for every difficulty in that range, making retargeting a
+ * no-op regardless of how fast or slow blocks arrive. */
+fn retarget_difficulty(parent: &DvbBlockHeader, child_timestamp: u64) -> usize {
+    let parent_diff = parent.difficulty as i64;
+    let elapsed = child_timestamp.saturating_sub(parent.timestamp) as i64;
+    let step = match elapsed.cmp(&TARGET_BLOCK_SECS) {
+        Ordering::Less => 1,
+        Ordering::Equal => 0,
+        Ordering::Greater => -1,
+    };
+    (parent_diff + step).max(MIN_DIFFICULTY as i64) as usize
+}
+
+fn tip_timestamp(chain: &DvbBlockchain) -> u64 {
+    chain.blocks.last().map(|block| block.header.timestamp).unwrap_or(0)
+}
+
+/* Decide whether `new_blockchain` should replace `current_blockchain`:
+ * strictly more total work wins outright; a tie falls back to whichever
+ * chain's tip landed first, and a tie on that falls back to the lower
+ * peer-id, so every honest node converges on the same answer. */
 pub fn process_new_blockchain(
-    new_blockchain: DvbBlockchain, 
-    current_blockchain: DvbBlockchain, 
+    new_blockchain: DvbBlockchain,
+    current_blockchain: DvbBlockchain,
+    new_chain_peer: Option<PeerId>,
+    local_peer: PeerId,
+    engine: ConsensusKind,
+    authority_round: Option<&AuthorityRoundEngine>,
 ) -> Result<DvbBlockchain, Box<dyn Error>> {
 
     println!("Got new blockchain: {new_blockchain:?}, verifying");
 
-    if new_blockchain.blocks.len() > current_blockchain.blocks.len()
-    {
-        println!("Received blockchain is heavier, verifying hashes");
-        match DvbBlockchain::verify_chain(&new_blockchain) {
-            Ok(()) => {
-                println!("Verification passed, accepting new blockchain as local");
+    match DvbBlockchain::verify_chain(&new_blockchain, engine, BlockSource::Network, authority_round) {
+        Ok(()) => {
+            let accept = match new_blockchain.total_difficulty().cmp(&current_blockchain.total_difficulty()) {
+                Ordering::Greater => true,
+                Ordering::Less => false,
+                Ordering::Equal => match tip_timestamp(&new_blockchain).cmp(&tip_timestamp(&current_blockchain)) {
+                    Ordering::Less => true,
+                    Ordering::Greater => false,
+                    Ordering::Equal => new_chain_peer.map(|p| p.to_bytes()) < Some(local_peer.to_bytes()),
+                },
+            };
+
+            if accept {
+                println!("Received blockchain is valid and wins fork choice, accepting as local");
                 Ok(new_blockchain)
-            }
-            Err(e) => {
-                println!("Blockchain verification failed {e:?}, ignoring");
+            } else {
+                println!("Received blockchain is valid but doesn't win fork choice, ignoring");
                 Ok(current_blockchain)
             }
         }
-    } else {
-        println!("Received blockchain is lighter, ignoring");
-        Ok(current_blockchain)
+        Err(e) => {
+            println!("Blockchain verification failed {e:?}, ignoring");
+            Ok(current_blockchain)
+        }
     }
 }
 
 pub async fn process_block(
-    block: DvbBlock, 
+    block: DvbBlock,
     cfg: &mut Config<'_>
 ) -> Result<(), Box<dyn Error>> {
     println!("Successfuly mined block: {:?}", block);
 
+    /* Attribute the block to its miner: sign `cur_hash` with the node's own
+     * wallet (`coinbase` is always this wallet's address) right after the
+     * seal succeeded, so `verify_block` can reject a forged coinbase. */
+    let mut block = block;
+    let signer = cfg.current_signer.clone().ok_or(DvbError::MiningError)?;
+    let author_sig = signer.sign_message(&block.header.cur_hash[..]).await.map_err(|_| DvbError::MiningError)?;
+    block.header.author_sig = Some(author_sig);
+
+    /* AuthorityRound blocks also need the producing validator's signature
+     * over the sealed header: `mining_task` stamps `step` but has no wallet
+     * to sign with, so it's attached here alongside `author_sig`. */
+    if cfg.consensus == ConsensusKind::AuthorityRound {
+        let mut unsigned = block.clone();
+        unsigned.header.validator_signature = None;
+        let data = serde_json::to_string(&unsigned)?;
+        let validator_signature = signer.sign_message(data.as_bytes()).await.map_err(|_| DvbError::MiningError)?;
+        block.header.validator_signature = Some(validator_signature);
+    }
+
     progress_state(
-        cfg.evm.db_mut(), 
-        &block, 
-        &mut cfg.transactions
+        &mut cfg.evm,
+        &block,
+        &mut cfg.transactions,
+        cfg.store.as_ref(),
+        cfg.trace_execution,
     )?;
 
-    /* Add the new block to the blockchain */
-    cfg.blockchain.append(block.clone())?;
+    /* Add the new block to the blockchain, persisting the delta if we have a store */
+    cfg.blockchain.append(block.clone(), cfg.consensus, BlockSource::Network, cfg.authority_round.as_ref())?;
+    if let Some(store) = &cfg.store {
+        store.add_block(&cfg.blockchain, &block, cfg.consensus, cfg.authority_round.as_ref())?;
+    }
 
     /* Get block proposition */
-    let signer = cfg.current_signer.clone().unwrap();
     let new_block = propose_block(
         signer.address(), 
         &cfg.blockchain, 
@@ -83,20 +160,50 @@ pub async fn process_block(
     Ok(())
 }
 
-fn validate_pow(mut block: DvbBlock, difficulty: usize) -> Result<bool, DvbError> {
+/* Number of leading zero bits in `hash`, i.e. how far the hash falls below
+ * the `2^(256-difficulty)` target threshold. */
+fn leading_zero_bits(hash: &[u8; 32]) -> usize {
+    let mut bits = 0;
+    for byte in hash.iter() {
+        if *byte == 0 {
+            bits += 8;
+        } else {
+            bits += byte.leading_zeros() as usize;
+            break;
+        }
+    }
+    bits
+}
+
+/* The SHA-256 digest of `block` with `cur_hash` zeroed out, used as the
+ * block hash itself by engines that don't search for a nonce (`NullEngine`). */
+pub(crate) fn hash_block(mut block: DvbBlock) -> Result<[u8; 32], DvbError> {
+    let mut hasher = Sha256::new();
+    block.header.cur_hash = [0; 32];
+    block.header.author_sig = None;
+
+    let data = serde_json::to_string(&block)?;
+    hasher.update(data);
+
+    hasher.finalize().try_into().map_err(|_| DvbError::HashConversionFailed)
+}
+
+pub(crate) fn validate_pow(mut block: DvbBlock, difficulty: usize) -> Result<bool, DvbError> {
     let mut hasher = Sha256::new();
 
     block.header.cur_hash = [0; 32];
+    /* Signed after the PoW search, so it was never part of the preimage */
+    block.header.author_sig = None;
 
     let data = serde_json::to_string(&block)?;
     hasher.update(data);
 
     let hash: [u8; 32] = match hasher.finalize().try_into() {
         Ok(arr) => arr,
-        Err(_) => return Err(DvbError::HashConversionFailed), 
+        Err(_) => return Err(DvbError::HashConversionFailed),
     };
 
-    if hash.iter().take(difficulty).all(|&b| b == 0) {
+    if leading_zero_bits(&hash) >= difficulty {
         block.header.cur_hash = hash;
         Ok(true)
     }
@@ -118,9 +225,18 @@ pub struct DvbBlockHeader {
     pub coinbase: Address,
     pub number: u64,
     pub nonce: u64,
+    /* Required leading zero *bits* in the PoW hash; see `retarget_difficulty` */
     pub difficulty: usize,
     pub cur_hash: [u8; 32],
     prev_hash: [u8; 32],
+    pub timestamp: u64,
+    /* Only set when the block was sealed by the AuthorityRound engine */
+    pub step: Option<u64>,
+    pub validator_signature: Option<Signature>,
+    /* `coinbase` signed over `cur_hash` by the miner's wallet, set right after
+     * PoW succeeds; lets `verify_block` attribute a block to its miner
+     * instead of trusting the unauthenticated `coinbase` field. */
+    pub author_sig: Option<Signature>,
 }
 
 impl DvbBlockHeader {
@@ -131,7 +247,11 @@ impl DvbBlockHeader {
             nonce: 0,
             difficulty: difficulty,
             cur_hash: [0; 32],
-            prev_hash: [0; 32]
+            prev_hash: [0; 32],
+            timestamp: now_secs(),
+            step: None,
+            validator_signature: None,
+            author_sig: None,
         }
     }
 
@@ -140,11 +260,23 @@ impl DvbBlockHeader {
             coinbase: coinbase,
             number: prev_block.header.number + 1,
             nonce: 0,
-            difficulty: difficulty,            
+            difficulty: difficulty,
             cur_hash: [0; 32],
-            prev_hash: prev_block.header.cur_hash
+            prev_hash: prev_block.header.cur_hash,
+            timestamp: now_secs(),
+            step: None,
+            validator_signature: None,
+            author_sig: None,
         }
     }
+
+    /* Like `no_prev`, but with an explicit timestamp instead of "now" --
+     * used for a chain-spec-driven genesis, which must be reproducible. */
+    pub fn genesis_with_timestamp(coinbase: Address, difficulty: usize, timestamp: u64) -> Self {
+        let mut header = Self::no_prev(coinbase, difficulty);
+        header.timestamp = timestamp;
+        header
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -171,18 +303,61 @@ impl DvbBlock {
 
     pub fn from_prev_block(coinbase: Address, transactions: &mut Vec<DvbTransaction>, prev_block: &DvbBlock, difficulty: usize) -> Self {
         let mut content = Vec::new();
-        
+
         /* We include at most top 3 transactions */
         let num_transactions = transactions.len().min(3);
         for i in 0..num_transactions {
             content.push(transactions[i].clone());
         }
-        
+
         Self {
             header: DvbBlockHeader::from_prev_block(coinbase, prev_block, difficulty),
             content: content
         }
     }
+
+    pub fn prev_hash_bytes(&self) -> [u8; 32] {
+        self.header.prev_hash
+    }
+
+    /* A chain-spec-driven genesis block, with no transactions */
+    pub fn genesis_with_timestamp(coinbase: Address, difficulty: usize, timestamp: u64) -> Self {
+        Self {
+            header: DvbBlockHeader::genesis_with_timestamp(coinbase, difficulty, timestamp),
+            content: Vec::new(),
+        }
+    }
+
+    /* Rebuild a block from a persisted storage row */
+    pub fn from_stored(
+        number: u64,
+        nonce: u64,
+        difficulty: usize,
+        coinbase: &[u8],
+        cur_hash: &[u8],
+        prev_hash: &[u8],
+        timestamp: u64,
+        content: Vec<DvbTransaction>,
+    ) -> Result<Self, DvbError> {
+        Ok(Self {
+            header: DvbBlockHeader {
+                coinbase: Address::from_slice(coinbase),
+                number,
+                nonce,
+                difficulty,
+                cur_hash: cur_hash.try_into().map_err(|_| DvbError::HashConversionFailed)?,
+                prev_hash: prev_hash.try_into().map_err(|_| DvbError::HashConversionFailed)?,
+                timestamp,
+                /* `step`, `validator_signature` and `author_sig` aren't columns in the
+                 * `blocks` table, so a restarted node can't re-attribute or
+                 * re-verify the authority/author of blocks mined before restart */
+                step: None,
+                validator_signature: None,
+                author_sig: None,
+            },
+            content,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -197,28 +372,120 @@ impl DvbBlockchain {
         }
     }
 
-    pub fn append(&mut self, block: DvbBlock) -> Result<(), DvbError> {
-        self.verify_block(block.clone())?;
+    /* Open (or create) the SQLite store at `path` and rebuild the chain from
+     * whatever blocks it already has, so a restarted node resumes mid-scenario
+     * instead of re-syncing from scratch. The returned `BlockStore` is the
+     * write-through target `append` callers should pass each new block to. */
+    pub fn open<P: AsRef<Path>>(path: P, engine: ConsensusKind) -> Result<(Self, BlockStore), DvbError> {
+        let store = BlockStore::open(path)?;
+        let mut chain = Self::new();
+        for block in store.load_blocks()? {
+            /* Stored blocks never carry `step`/`validator_signature` (no column
+             * for either), so `verify_block` never consults the engine for
+             * them; there's nothing to pass here. */
+            chain.append(block, engine, BlockSource::Stored, None)?;
+        }
+        Ok((chain, store))
+    }
+
+    pub fn append(&mut self, block: DvbBlock, engine: ConsensusKind, source: BlockSource, authority_round: Option<&AuthorityRoundEngine>) -> Result<(), DvbError> {
+        self.verify_block(block.clone(), engine, source, authority_round)?;
         /* update internal state */
         self.blocks.push(block);
         Ok(())
     }
-    
-    fn verify_block(&self, block: DvbBlock) -> Result<(), DvbError> {
-        let pow_ok = validate_pow(block.clone(), block.header.difficulty)?;
-        
-        // check POW
-        if !pow_ok {
-            return Err(DvbError::InvalidProofOfWork);
-        }        
+
+    /* The block whose `cur_hash` matches `block`'s `prev_hash`, if we have it */
+    fn find_parent(&self, block: &DvbBlock) -> Option<&DvbBlock> {
+        self.blocks.iter().find(|b| b.header.cur_hash == block.header.prev_hash)
+    }
+
+    /* Seal verification is delegated to the `ConsensusEngine` the chain was
+     * configured with; only `ProofOfWork` retargets difficulty, since that's
+     * the only engine where difficulty is a meaningful knob. `AuthorityRound`
+     * is authenticated by `AuthorityRoundEngine::verify_seal`, which recomputes
+     * the expected validator for the claimed step and checks the signature
+     * recovers to it -- the validator set lives in the chain spec rather than
+     * on the wire, so the caller must hold an `AuthorityRoundEngine` for this
+     * network and pass it in as `authority_round`.
+     *
+     * `source` says whether `block` was replayed from the local store or
+     * received over the network -- only the latter is required to carry
+     * `author_sig`/`step`/`validator_signature`, since the `blocks` table has
+     * no column for any of them. */
+    pub(crate) fn verify_block(&self, block: DvbBlock, engine: ConsensusKind, source: BlockSource, authority_round: Option<&AuthorityRoundEngine>) -> Result<(), DvbError> {
+        let parent = self.find_parent(&block);
+
+        match engine {
+            ConsensusKind::ProofOfWork => ProofOfWorkEngine.verify_seal(&block, None)?,
+            ConsensusKind::Null => NullEngine.verify_seal(&block, None)?,
+            ConsensusKind::AuthorityRound => {
+                /* Genesis has no step to have been sealed at, and stored
+                 * blocks never persisted step/validator_signature (no column
+                 * for either) -- both are exempt, same as `author_sig` below. */
+                if parent.is_some() && source == BlockSource::Network {
+                    let ar = authority_round.ok_or(DvbError::InvalidEvent)?;
+                    ar.verify_seal(&block, parent)?;
+                }
+            }
+        }
+
+        /* Genesis has no parent to retarget or order against */
+        if let Some(parent) = parent {
+            if engine == ConsensusKind::ProofOfWork {
+                let expected_difficulty = retarget_difficulty(&parent.header, block.header.timestamp);
+                if block.header.difficulty != expected_difficulty {
+                    return Err(DvbError::InvalidDifficulty);
+                }
+            } else if block.header.difficulty != parent.header.difficulty {
+                /* `Null`/`AuthorityRound` don't retarget, but `difficulty` still
+                 * feeds `total_difficulty()`, which fork choice compares --
+                 * without pinning it to the parent's, a sealer on either engine
+                 * could stamp an arbitrarily large value on every block and win
+                 * fork choice against honest peers for free. */
+                return Err(DvbError::InvalidDifficulty);
+            }
+
+            if block.header.timestamp <= parent.header.timestamp {
+                return Err(DvbError::InvalidDifficulty);
+            }
+
+            /* Authenticate the unauthenticated `coinbase` field: recover the
+             * signer of `author_sig` over `cur_hash` and require it to be the
+             * claimed miner, so a forged-coinbase block propagated over
+             * gossipsub gets rejected rather than silently attributed. Blocks
+             * replayed from the store never had `author_sig` persisted, so
+             * they're trusted instead of re-authenticated. */
+            if source == BlockSource::Network {
+                let author_sig = block.header.author_sig.ok_or(DvbError::InvalidEvent)?;
+                let recovered = author_sig
+                    .recover_address_from_msg(&block.header.cur_hash[..])
+                    .map_err(|_| DvbError::InvalidEvent)?;
+                if recovered != block.header.coinbase {
+                    return Err(DvbError::InvalidEvent);
+                }
+            }
+        }
+
         Ok(())
     }
 
-    pub fn verify_chain(chain: &DvbBlockchain) -> Result<(), DvbError> {
-        
-        /* First check the pow of each block */
+    /* Total proof-of-work behind the chain: 2^difficulty per block (difficulty
+     * is a count of required leading zero bits), summed as u128 so a long
+     * chain of high-difficulty blocks can't overflow. This is what fork
+     * choice compares, not raw block count. */
+    pub fn total_difficulty(&self) -> u128 {
+        self.blocks
+            .iter()
+            .map(|block| 2u128.saturating_pow(block.header.difficulty as u32))
+            .fold(0u128, |acc, work| acc.saturating_add(work))
+    }
+
+    pub fn verify_chain(chain: &DvbBlockchain, engine: ConsensusKind, source: BlockSource, authority_round: Option<&AuthorityRoundEngine>) -> Result<(), DvbError> {
+
+        /* First check the seal of each block */
         for block in chain.blocks.iter() {
-            chain.verify_block(block.clone())?;
+            chain.verify_block(block.clone(), engine, source, authority_round)?;
         }
 
         /* Then check the links */
@@ -253,7 +520,7 @@ pub fn proof_of_work(
             Err(_) => return Err(DvbError::HashConversionFailed), 
         };
 
-        if hash.iter().take(difficulty).all(|&b| b == 0) {
+        if leading_zero_bits(&hash) >= difficulty {
             block.header.cur_hash = hash;
             println!("proof_of_work::finish");
             return Ok(block);
@@ -264,7 +531,13 @@ pub fn proof_of_work(
 
 pub async fn mining_task(
     mut command_rx: mpsc::Receiver<DvbCommand>,
-    block_tx: mpsc::Sender<DvbBlock>
+    block_tx: mpsc::Sender<DvbBlock>,
+    engine: ConsensusKind,
+    /* Only set (and only consulted) under `ConsensusKind::AuthorityRound`, to
+     * decide whose turn it is and stamp `step`. It carries no signer: the
+     * actual `validator_signature` is async and wallet-bound, so it's
+     * attached by `process_block` instead, alongside `author_sig`. */
+    authority_round: Option<AuthorityRoundEngine>,
     ) -> Result<(), DvbError> {
 
     let mut current_block: Option<DvbBlock> = None;
@@ -300,8 +573,26 @@ pub async fn mining_task(
 
                 println!("Starting the mining for a new block");
                 let block = current_block.clone().unwrap();
-                let difficulty = block.header.difficulty;
-                Some(proof_of_work(block, difficulty).ok()?)
+                match engine {
+                    ConsensusKind::ProofOfWork => {
+                        let difficulty = block.header.difficulty;
+                        Some(proof_of_work(block, difficulty).ok()?)
+                    },
+                    ConsensusKind::Null => Some(NullEngine.seal_block(block).ok()?),
+                    ConsensusKind::AuthorityRound => {
+                        let ar = authority_round.as_ref()?;
+                        let timestamp = now_secs();
+                        let step = ar.step_for(timestamp);
+                        if ar.primary_for_step(step) != block.header.coinbase {
+                            return None // not this validator's turn yet
+                        }
+
+                        let mut sealed = block;
+                        sealed.header.step = Some(step);
+                        sealed.header.cur_hash = hash_block(sealed.clone()).ok()?;
+                        Some(sealed)
+                    },
+                }
             } => {
                 match mined_block {
                     Some(mined_block) => {
@@ -317,25 +608,128 @@ pub async fn mining_task(
 }
 
 pub async fn propose_block(
-    coinbase: Address, 
+    coinbase: Address,
     blockchain: &DvbBlockchain,
-    difficulty: usize,
+    /* Only used for the genesis block; every later block's difficulty is
+     * retargeted from its parent instead. */
+    genesis_difficulty: usize,
     transactions: &mut Vec<DvbTransaction>
 ) -> Result<DvbBlock, Box<dyn std::error::Error>> {
-    
+
     println!("Building a block proposal");
     if blockchain.blocks.len() == 0 {
-        Ok(DvbBlock::no_prev(coinbase, transactions, difficulty))
+        Ok(DvbBlock::no_prev(coinbase, transactions, genesis_difficulty))
     }
     else {
         let mined_block = blockchain.blocks.last().unwrap();
-        
+        let difficulty = retarget_difficulty(&mined_block.header, now_secs());
+
         let next = DvbBlock::from_prev_block(
             coinbase,
-            transactions, 
+            transactions,
             &mined_block,
             difficulty
         );
         Ok(next)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::wallet::LocalWallet;
+
+    /* Build a chain of `len` blocks, all sealed with `NullEngine` (no PoW
+     * search) and signed by `signer`, whose address is every block's
+     * coinbase -- enough to exercise fork choice without paying for a real
+     * PoW search in a test. */
+    async fn mine_chain(signer: &LocalWallet, len: usize, difficulty: usize) -> DvbBlockchain {
+        let coinbase = signer.address();
+        let mut transactions: Vec<DvbTransaction> = Vec::new();
+        let mut chain = DvbBlockchain::new();
+
+        for i in 0..len {
+            let mut block = if i == 0 {
+                DvbBlock::no_prev(coinbase, &mut transactions, difficulty)
+            } else {
+                let parent = chain.blocks.last().unwrap().clone();
+                let mut block = DvbBlock::from_prev_block(coinbase, &mut transactions, &parent, difficulty);
+                block.header.timestamp = parent.header.timestamp + 1;
+                block
+            };
+
+            block = NullEngine.seal_block(block).unwrap();
+            let author_sig = signer.sign_message(&block.header.cur_hash[..]).await.unwrap();
+            block.header.author_sig = Some(author_sig);
+
+            chain.append(block, ConsensusKind::Null, BlockSource::Network, None).unwrap();
+        }
+        chain
+    }
+
+    #[test]
+    fn total_difficulty_sums_work_not_blocks() {
+        assert_eq!(DvbBlockchain::new().total_difficulty(), 0);
+
+        /* Two blocks at difficulty 3 and 5 carry 2^3 + 2^5 units of work */
+        let mut transactions: Vec<DvbTransaction> = Vec::new();
+        let chain = DvbBlockchain {
+            blocks: vec![
+                DvbBlock::no_prev(Address::ZERO, &mut transactions, 3),
+                DvbBlock::no_prev(Address::ZERO, &mut transactions, 5),
+            ],
+        };
+        assert_eq!(chain.total_difficulty(), 40);
+    }
+
+    /* The classic "longer but lower-work" reorg attack: flooding many
+     * low-difficulty blocks must not beat a shorter, heavier chain. */
+    #[tokio::test]
+    async fn fork_choice_prefers_more_work_over_more_blocks() {
+        let signer = LocalWallet::random();
+        let current = mine_chain(&signer, 10, 1).await;
+        let incoming = mine_chain(&signer, 2, 8).await;
+
+        assert!(incoming.total_difficulty() > current.total_difficulty());
+        assert!(incoming.blocks.len() < current.blocks.len());
+
+        let incoming_work = incoming.total_difficulty();
+        let local_peer = PeerId::random();
+        let result = process_new_blockchain(
+            incoming,
+            current,
+            Some(PeerId::random()),
+            local_peer,
+            ConsensusKind::Null,
+            None,
+        ).unwrap();
+
+        assert_eq!(result.total_difficulty(), incoming_work);
+        assert_eq!(result.blocks.len(), 2);
+    }
+
+    /* The classic invalid-PoW reorg attack: a chain that claims more work but
+     * fails `verify_chain` (here, a seal that no longer matches its claimed
+     * hash) must be rejected outright, keeping the current chain. */
+    #[tokio::test]
+    async fn fork_choice_rejects_invalid_chain_even_if_heavier() {
+        let signer = LocalWallet::random();
+        let current = mine_chain(&signer, 2, 1).await;
+        let mut incoming = mine_chain(&signer, 2, 8).await;
+        incoming.blocks.last_mut().unwrap().header.cur_hash[0] ^= 0xFF;
+
+        let current_work = current.total_difficulty();
+        let local_peer = PeerId::random();
+        let result = process_new_blockchain(
+            incoming,
+            current,
+            Some(PeerId::random()),
+            local_peer,
+            ConsensusKind::Null,
+            None,
+        ).unwrap();
+
+        assert_eq!(result.total_difficulty(), current_work);
+        assert_eq!(result.blocks.len(), 2);
+    }
 }
\ No newline at end of file
@@ -30,22 +30,23 @@ use libp2p::{
 };
 
 use crate::consensus::{
-    IshIshBlockchain, 
-    IshIshCommand
+    DvbBlockchain,
+    DvbCommand
 };
 
 use crate::config::Config;
-use crate::common::IshIshError;
+use crate::common::DvbError;
 use crate::consensus::{
     process_new_blockchain,
     propose_block
 };
 use crate::settlement::{
-    IshIshTransaction,
-    refresh_state
+    DvbTransaction,
+    refresh_state,
+    load_state_from_store
 };
 
-use crate::common::ISHISH_TOPIC;
+use crate::common::DVB_TOPIC;
 
 // We create a custom network behaviour that combines Gossipsub and Mdns.
 #[derive(NetworkBehaviour)]
@@ -70,7 +71,7 @@ pub async fn swarm_publish(
 pub async fn broadcast_new_blockchain(
     swarm: &mut libp2p::Swarm<IshIshClientBehavior>, 
     topic: &IdentTopic, 
-    blockchain: &IshIshBlockchain
+    blockchain: &DvbBlockchain
 ) -> Result<(), Box<dyn Error>> {
     /* Broadcast info about the new blockchain via data availability layer */
     let mut line = String::from("NBM");
@@ -86,7 +87,7 @@ pub async fn broadcast_new_blockchain(
 pub async fn broadcast_new_transaction(
     swarm: &mut libp2p::Swarm<IshIshClientBehavior>, 
     topic: &IdentTopic, 
-    transaction: &IshIshTransaction
+    transaction: &DvbTransaction
 ) -> Result<(), Box<dyn Error>> {
     /* Broadcast info about the new blockchain via data availability layer */
     let mut line = String::from("TRA");
@@ -137,7 +138,7 @@ pub fn build_swarm(
         .build();
 
     // Create a Gossipsub topic
-    let topic = gossipsub::IdentTopic::new(ISHISH_TOPIC);
+    let topic = gossipsub::IdentTopic::new(DVB_TOPIC);
 
     // subscribes to our topic
     swarm.behaviour_mut().gossipsub.subscribe(&topic)?;
@@ -150,25 +151,48 @@ async fn process_blockchain_event(
 ) -> Result<(), Box<dyn Error>> {
 
     if let libp2p::gossipsub::Event::Message { message, ..} = event {
-        match IshIshBlockchainEvent::try_from(&message.data)? {
-            IshIshBlockchainEvent::NewBlockMined(serialized) => {
+        match DvbBlockchainEvent::try_from(&message.data)? {
+            DvbBlockchainEvent::NewBlockMined(serialized) => {
                 /* Deserializing */
-                let new_blockchain: IshIshBlockchain = serde_json::from_str(&serialized)?;
+                let new_blockchain: DvbBlockchain = serde_json::from_str(&serialized)?;
     
                 /* Processing, consume both and return selected */
+                let local_peer = *cfg.swarm.local_peer_id();
+                let previous_tip = cfg.blockchain.blocks.last().map(|b| b.header.cur_hash);
                 cfg.blockchain = process_new_blockchain(
-                    new_blockchain, 
-                    mem::take(&mut cfg.blockchain)
+                    new_blockchain,
+                    mem::take(&mut cfg.blockchain),
+                    message.source,
+                    local_peer,
+                    cfg.consensus,
+                    cfg.authority_round.as_ref(),
                 )?;
-    
-                /* We need to recreate the internal state */
+
+                /* A reorg swapped in a heavier chain: write it through to the
+                 * store so a restart doesn't reload the stale pre-reorg tip
+                 * (`store.add_block` is only called for self-mined blocks and a
+                 * chain-spec genesis, so without this the on-disk chain silently diverges
+                 * from the in-memory one on every accepted fork). */
+                let new_tip = cfg.blockchain.blocks.last().map(|b| b.header.cur_hash);
+                if new_tip != previous_tip {
+                    if let Some(store) = &cfg.store {
+                        store.replace_blocks(&cfg.blockchain.blocks)?;
+                    }
+                }
+
+                /* We need to recreate the internal state. If we have a persisted
+                 * store, materialize balances from it instead of replaying the
+                 * whole chain from genesis. */
                 let new_state = CacheDB::new(EmptyDB::default());
                 cfg.evm = Evm::builder().with_db(new_state).build();
-                refresh_state(
-                    &mut cfg.evm.db_mut(), 
-                    &cfg.blockchain, 
-                    &mut cfg.transactions
-                )?;
+                match &cfg.store {
+                    Some(store) => load_state_from_store(cfg.evm.db_mut(), store)?,
+                    None => refresh_state(
+                        &mut cfg.evm,
+                        &cfg.blockchain,
+                        &mut cfg.transactions
+                    )?,
+                }
     
                 /* Get block proposition */
                 if let Some(signer) = &cfg.current_signer
@@ -179,24 +203,31 @@ async fn process_blockchain_event(
                         cfg.difficulty, 
                         &mut cfg.transactions
                     ).await?;                                    
-                    cfg.command_tx.send(IshIshCommand::MineBlock(new_block)).await?;
+                    cfg.command_tx.send(DvbCommand::MineBlock(new_block)).await?;
 
                 } else {
                     println!("No wallet opened, can't propose block");
                 };
             },
-            IshIshBlockchainEvent::NewSignedTransaction(transaction_str) => {
-                let transaction: IshIshTransaction = serde_json::from_str(&transaction_str)?;
-    
+            DvbBlockchainEvent::NewSignedTransaction(transaction_str) => {
+                let transaction: DvbTransaction = serde_json::from_str(&transaction_str)?;
+
                 println!("Got new transaction: {:?}", transaction);
-    
+
+                /* Reject anything whose signature doesn't recover to `from` before
+                 * it ever touches the pool or a balance */
+                if !transaction.verify_signature() {
+                    println!("Transaction signature verification failed, dropping: {:?}", transaction);
+                    return Ok(());
+                }
+
                 /* Add to local pool */
                 cfg.transactions.push(transaction);
                 println!("Transaction added to local pool");
                 println!("Current pool: {:?}", cfg.transactions);
-    
+
             },
-            IshIshBlockchainEvent::SthElse((msg,re)) => {
+            DvbBlockchainEvent::SthElse((msg,re)) => {
                 println!("Something else: {msg} {re}");
             }
         }
@@ -235,16 +266,16 @@ pub async fn process_event(
 
 }
 
-pub enum IshIshBlockchainEvent<'a> {
+pub enum DvbBlockchainEvent<'a> {
     NewBlockMined(&'a str),
     SthElse((&'a str, &'a str)),
     NewSignedTransaction(&'a str),
 }
 
-impl<'a> TryFrom<&'a Vec<u8>> for IshIshBlockchainEvent<'a> {
-    type Error = IshIshError;
+impl<'a> TryFrom<&'a Vec<u8>> for DvbBlockchainEvent<'a> {
+    type Error = DvbError;
 
-    fn try_from(value: &'a Vec<u8>) -> Result<Self, IshIshError> 
+    fn try_from(value: &'a Vec<u8>) -> Result<Self, DvbError> 
     {
         let value_str = std::str::from_utf8(value)?;
     
@@ -252,12 +283,12 @@ impl<'a> TryFrom<&'a Vec<u8>> for IshIshBlockchainEvent<'a> {
 
         match header {
             "NBM" => {
-                return Ok(IshIshBlockchainEvent::NewBlockMined(message))
+                return Ok(DvbBlockchainEvent::NewBlockMined(message))
             },
             "TRA" => {
-                return Ok(IshIshBlockchainEvent::NewSignedTransaction(message))
+                return Ok(DvbBlockchainEvent::NewSignedTransaction(message))
             },
-            _ => return Ok(IshIshBlockchainEvent::SthElse((header, message)))
+            _ => return Ok(DvbBlockchainEvent::SthElse((header, message)))
         }
     }
 }
@@ -0,0 +1,159 @@
+/* Pluggable consensus. `ConsensusEngine` is the seam between the mining loop
+ * and whatever rule decides who gets to produce the next block: proof of
+ * work today, Authority Round as an alternative that removes the need to
+ * burn CPU in demos (and gives the vulnerable chain a second thing to
+ * attack: step equivocation, validator impersonation). */
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::Address;
+use alloy::signers::{wallet::LocalWallet, Signer};
+use serde::{Serialize, Deserialize};
+
+use crate::common::DvbError;
+use crate::consensus::{hash_block, validate_pow, proof_of_work, DvbBlock};
+
+/* Selects which engine a node runs; read from `Config` when proposing/verifying
+ * blocks, or from a chain spec's `engine.kind` on startup. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConsensusKind {
+    ProofOfWork,
+    AuthorityRound,
+    /* Instant-seal: no nonce search, no authority set, `cur_hash` is just the
+     * block hash. For fast CI/local runs where PoW's mining delay only gets
+     * in the way. */
+    Null,
+}
+
+/* Where a block being verified came from. The `blocks` table has no column
+ * for `author_sig` (it's attached after the seal, never persisted), so a
+ * block replayed from the local store can never carry one -- `verify_block`
+ * only demands it for blocks arriving over the network, where it's the only
+ * thing authenticating the unauthenticated `coinbase` field. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockSource {
+    Stored,
+    Network,
+}
+
+pub trait ConsensusEngine {
+    fn seal_block(&self, block: DvbBlock) -> Result<DvbBlock, DvbError>;
+    fn verify_seal(&self, block: &DvbBlock, parent: Option<&DvbBlock>) -> Result<(), DvbError>;
+}
+
+pub struct ProofOfWorkEngine;
+
+impl ConsensusEngine for ProofOfWorkEngine {
+    fn seal_block(&self, block: DvbBlock) -> Result<DvbBlock, DvbError> {
+        let difficulty = block.header.difficulty;
+        proof_of_work(block, difficulty)
+    }
+
+    fn verify_seal(&self, block: &DvbBlock, _parent: Option<&DvbBlock>) -> Result<(), DvbError> {
+        if validate_pow(block.clone(), block.header.difficulty)? {
+            Ok(())
+        } else {
+            Err(DvbError::InvalidProofOfWork)
+        }
+    }
+}
+
+/* Instant-seal: the block hash itself, no nonce search required. Every block
+ * is valid as long as `cur_hash` matches the claimed content -- useful for
+ * demos and tests where PoW's mining delay would only get in the way. */
+pub struct NullEngine;
+
+impl ConsensusEngine for NullEngine {
+    fn seal_block(&self, mut block: DvbBlock) -> Result<DvbBlock, DvbError> {
+        block.header.cur_hash = hash_block(block.clone())?;
+        Ok(block)
+    }
+
+    fn verify_seal(&self, block: &DvbBlock, _parent: Option<&DvbBlock>) -> Result<(), DvbError> {
+        if hash_block(block.clone())? == block.header.cur_hash {
+            Ok(())
+        } else {
+            Err(DvbError::InvalidProofOfWork)
+        }
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before UNIX epoch")
+        .as_secs()
+}
+
+/* Authority Round: validators take turns proposing blocks. The producer for
+ * `step = now / step_duration` is `validators[step % validators.len()]`. */
+#[derive(Clone)]
+pub struct AuthorityRoundEngine {
+    pub validators: Vec<Address>,
+    pub step_duration: u64,
+    pub signer: Option<LocalWallet>,
+}
+
+impl AuthorityRoundEngine {
+    pub fn step_for(&self, timestamp: u64) -> u64 {
+        timestamp / self.step_duration
+    }
+
+    pub fn primary_for_step(&self, step: u64) -> Address {
+        self.validators[(step as usize) % self.validators.len()]
+    }
+
+    pub async fn seal_at(&self, mut block: DvbBlock, timestamp: u64) -> Result<DvbBlock, DvbError> {
+        let signer = self.signer.as_ref().ok_or(DvbError::MiningError)?;
+        let step = self.step_for(timestamp);
+
+        block.header.step = Some(step);
+        block.header.validator_signature = None;
+
+        let data = serde_json::to_string(&block)?;
+        let signature = signer.sign_message(data.as_bytes()).await.map_err(|_| DvbError::MiningError)?;
+        block.header.validator_signature = Some(signature);
+        Ok(block)
+    }
+}
+
+impl ConsensusEngine for AuthorityRoundEngine {
+    fn seal_block(&self, _block: DvbBlock) -> Result<DvbBlock, DvbError> {
+        /* Sealing requires signing, which is async; use `seal_at` instead */
+        Err(DvbError::MiningError)
+    }
+
+    fn verify_seal(&self, block: &DvbBlock, parent: Option<&DvbBlock>) -> Result<(), DvbError> {
+        let step = block.header.step.ok_or(DvbError::InvalidEvent)?;
+
+        /* A parent without a step (a chain-spec genesis, or a block reloaded
+         * from the store, which has no column for it) imposes no ordering */
+        if let Some(parent_step) = parent.and_then(|parent| parent.header.step) {
+            if step <= parent_step {
+                return Err(DvbError::InvalidEvent);
+            }
+        }
+
+        let current_step = self.step_for(unix_timestamp());
+        if step > current_step + 1 {
+            return Err(DvbError::InvalidEvent);
+        }
+
+        let expected_author = self.primary_for_step(step);
+        let signature = block.header.validator_signature.ok_or(DvbError::InvalidEvent)?;
+
+        let mut unsigned = block.clone();
+        unsigned.header.validator_signature = None;
+        let data = serde_json::to_string(&unsigned)?;
+
+        let recovered = signature
+            .recover_address_from_msg(data.as_bytes())
+            .map_err(|_| DvbError::InvalidEvent)?;
+
+        if recovered != expected_author {
+            return Err(DvbError::InvalidEvent);
+        }
+
+        Ok(())
+    }
+}
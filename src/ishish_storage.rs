@@ -0,0 +1,87 @@
+/* SQLite-backed persistence for the old ishishnet chain, mirroring
+ * storage.rs's approach for the Dvb lineage. IshIshBlockHeader has no
+ * coinbase/Address (that only exists on the newer DvbBlockHeader), so the
+ * schema is narrower: number, nonce, difficulty, cur_hash, prev_hash and
+ * the raw content string. */
+
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use crate::ishishnet::{IshIshBlock, IshIshError};
+
+pub struct IshIshBlockStore {
+    conn: Connection,
+}
+
+impl IshIshBlockStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, IshIshError> {
+        let conn = Connection::open(path).map_err(|_| IshIshError::ParseError)?;
+        let store = Self { conn };
+        store.init_db()?;
+        Ok(store)
+    }
+
+    fn init_db(&self) -> Result<(), IshIshError> {
+        self.conn
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS blocks (
+                    number      INTEGER PRIMARY KEY,
+                    nonce       INTEGER NOT NULL,
+                    difficulty  INTEGER NOT NULL,
+                    cur_hash    BLOB NOT NULL UNIQUE,
+                    prev_hash   BLOB NOT NULL,
+                    content     TEXT NOT NULL
+                );",
+            )
+            .map_err(|_| IshIshError::ParseError)?;
+        Ok(())
+    }
+
+    /* `number` is just this block's position in the chain; the header
+     * itself carries no block number. */
+    pub fn add_block(&self, number: u64, block: &IshIshBlock) -> Result<(), IshIshError> {
+        self.conn
+            .execute(
+                "INSERT INTO blocks (number, nonce, difficulty, cur_hash, prev_hash, content)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    number,
+                    block.header.nonce,
+                    block.header.difficulty as i64,
+                    block.header.cur_hash.as_slice(),
+                    block.prev_hash_bytes().as_slice(),
+                    block.content(),
+                ],
+            )
+            .map_err(|_| IshIshError::ParseError)?;
+        Ok(())
+    }
+
+    pub fn load_blocks(&self) -> Result<Vec<IshIshBlock>, IshIshError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT nonce, difficulty, cur_hash, prev_hash, content FROM blocks ORDER BY number ASC")
+            .map_err(|_| IshIshError::ParseError)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let nonce: u64 = row.get(0)?;
+                let difficulty: i64 = row.get(1)?;
+                let cur_hash: Vec<u8> = row.get(2)?;
+                let prev_hash: Vec<u8> = row.get(3)?;
+                let content: String = row.get(4)?;
+                Ok((nonce, difficulty, cur_hash, prev_hash, content))
+            })
+            .map_err(|_| IshIshError::ParseError)?;
+
+        let mut blocks = Vec::new();
+        for row in rows {
+            let (nonce, difficulty, cur_hash, prev_hash, content) = row.map_err(|_| IshIshError::ParseError)?;
+            blocks.push(IshIshBlock::from_stored(
+                nonce, difficulty as usize, &cur_hash, &prev_hash, content,
+            )?);
+        }
+        Ok(blocks)
+    }
+}
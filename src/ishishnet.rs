@@ -1,5 +1,5 @@
 use std::{
-    convert::TryFrom,
+    convert::{TryFrom, TryInto},
     error::Error,
     fmt::{
         Display,
@@ -11,10 +11,31 @@ use std::{
     }
 };
 
+use std::{
+    env,
+    fs,
+    path::PathBuf
+};
+
 use sha2::{Sha256, Digest};
 use serde::{Serialize, Deserialize};
 use rand::Rng;
 
+pub const ISHISH_HOME: &str = ".ishish";
+
+pub fn ensure_ishish_home() -> Result<PathBuf, IshIshError> {
+    let mut path = PathBuf::new();
+    let home_dir = env::var_os("HOME").ok_or(IshIshError::ParseError)?;
+    path.push(home_dir);
+    path.push(ISHISH_HOME);
+
+    if !path.exists() {
+        println!("Creating ishish home dir");
+        fs::create_dir_all(&path).map_err(|_| IshIshError::ParseError)?;
+    }
+    Ok(path)
+}
+
 #[derive(Debug)]
 pub enum IshIshError {
     ParseError,
@@ -143,26 +164,66 @@ impl IshIshBlock {
             content: content
         }
     }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn prev_hash_bytes(&self) -> [u8; 32] {
+        self.header.prev_hash
+    }
+
+    /* Rebuild a block from a row persisted by `IshIshBlockStore` */
+    pub fn from_stored(
+        nonce: u64,
+        difficulty: usize,
+        cur_hash: &[u8],
+        prev_hash: &[u8],
+        content: String,
+    ) -> Result<Self, IshIshError> {
+        Ok(Self {
+            header: IshIshBlockHeader {
+                nonce,
+                difficulty,
+                cur_hash: cur_hash.try_into().map_err(|_| IshIshError::HashConversionFailed)?,
+                prev_hash: prev_hash.try_into().map_err(|_| IshIshError::HashConversionFailed)?,
+            },
+            content,
+        })
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct IshIshBlockchain {
     pub blocks: Vec<IshIshBlock>,
+    pub last_block: Option<IshIshBlock>,
 }
 
 impl IshIshBlockchain {
     pub fn new() -> Self {
         Self {
-            blocks: Vec::new()
+            blocks: Vec::new(),
+            last_block: None,
         }
     }
 
-    pub fn append(&mut self, mut block: IshIshBlock) -> Result<(), IshIshError> {
+    /* Rebuild the chain from whatever `store` has on disk, so a restarted
+     * node resumes instead of re-syncing from scratch over gossipsub. */
+    pub fn from_store(store: &crate::ishish_storage::IshIshBlockStore) -> Result<Self, IshIshError> {
+        let blocks = store.load_blocks()?;
+        let mut chain = Self { blocks, last_block: None };
+        chain.verify_chain()?;
+        chain.last_block = chain.blocks.last().cloned();
+        Ok(chain)
+    }
+
+    pub fn append(&mut self, block: IshIshBlock) -> Result<(), IshIshError> {
         self.verify_block(block.clone())?;
         self.blocks.push(block);
+        self.last_block = self.blocks.last().cloned();
         Ok(())
     }
-    
+
     fn verify_block(&self, block: IshIshBlock) -> Result<(), IshIshError> {
         let pow_ok = validate_pow(block.clone(), block.header.difficulty)?;
         
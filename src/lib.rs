@@ -0,0 +1,11 @@
+pub mod common;
+pub mod config;
+pub mod consensus;
+pub mod settlement;
+pub mod command;
+pub mod data;
+pub mod storage;
+pub mod engine;
+pub mod rpc;
+pub mod trace;
+pub mod spec;
@@ -31,9 +31,14 @@ use ishishnet::{
     IshIshBlockchainEvent,
     IshIshBlockchain,
     IshIshBlock,
-    IshIshCommand
+    IshIshCommand,
+    ensure_ishish_home
 };
 
+mod ishish_storage;
+
+use ishish_storage::IshIshBlockStore;
+
 mod mining;
 
 use mining::{
@@ -70,30 +75,48 @@ async fn broadcast_new_blockchain(
     Ok(())
 }
 
-/* consumes both blockchains */
+/* Total proof-of-work behind the chain: 2^(8*difficulty) per block, since
+ * `validate_pow` here still tests whole leading zero bytes. */
+fn total_difficulty(chain: &IshIshBlockchain) -> u128 {
+    chain
+        .blocks
+        .iter()
+        .map(|block| 2u128.saturating_pow(8 * block.header.difficulty as u32))
+        .fold(0u128, |acc, work| acc.saturating_add(work))
+}
+
+/* consumes both blockchains. Decides the canonical chain on total work, not
+ * block count, so flooding low-difficulty blocks can't win a fork; a tie
+ * falls back to the lower peer-id. */
 fn process_new_blockchain(
-    new_blockchain: IshIshBlockchain, 
-    current_blockchain: IshIshBlockchain, 
+    new_blockchain: IshIshBlockchain,
+    current_blockchain: IshIshBlockchain,
+    new_chain_peer: Option<libp2p::PeerId>,
+    local_peer: libp2p::PeerId,
 ) -> Result<IshIshBlockchain, Box<dyn Error>> {
 
     println!("Got new blockchain: {new_blockchain:?}, verifying");
 
-    if new_blockchain.blocks.len() > current_blockchain.blocks.len()
-    {
-        println!("Received blockchain is heavier, verifying hashes");
-        match new_blockchain.verify_chain() {
-            Ok(()) => {
-                println!("Verification passed, accepting new blockchain as local");
+    match new_blockchain.verify_chain() {
+        Ok(()) => {
+            let accept = match total_difficulty(&new_blockchain).cmp(&total_difficulty(&current_blockchain)) {
+                std::cmp::Ordering::Greater => true,
+                std::cmp::Ordering::Less => false,
+                std::cmp::Ordering::Equal => new_chain_peer.map(|p| p.to_bytes()) < Some(local_peer.to_bytes()),
+            };
+
+            if accept {
+                println!("Received blockchain is valid and wins fork choice, accepting as local");
                 Ok(new_blockchain)
-            }
-            Err(e) => {
-                println!("Blockchain verification failed {e:?}, ignoring");
+            } else {
+                println!("Received blockchain is valid but doesn't win fork choice, ignoring");
                 Ok(current_blockchain)
             }
         }
-    } else {
-        println!("Received blockchain is lighter, ignoring");
-        Ok(current_blockchain)
+        Err(e) => {
+            println!("Blockchain verification failed {e:?}, ignoring");
+            Ok(current_blockchain)
+        }
     }
 }
 
@@ -156,8 +179,14 @@ async fn main() -> Result<(), Box<dyn Error>> {
     swarm.listen_on("/ip4/0.0.0.0/tcp/0".parse()?)?;
 
 
-    /* Local blockchain */
-    let mut my_blockchain = IshIshBlockchain::new();
+    /* Local blockchain, persisted to blockchain.db under the ishish home dir so
+     * a restart resumes instead of re-syncing from scratch over gossipsub */
+    let ishish_home = ensure_ishish_home()?;
+    let mut db_path = ishish_home.clone();
+    db_path.push("blockchain.db");
+    let store = IshIshBlockStore::open(db_path)?;
+
+    let mut my_blockchain = IshIshBlockchain::from_store(&store)?;
 
     println!("Starting the local mining task");
     let (command_tx, command_rx) = mpsc::channel(100);
@@ -199,9 +228,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
                 println!("Successfuly mined block: {:?}", mined_block);
 
-                /* Add the new block to my_blockchain */
+                /* Add the new block to my_blockchain, then write it through to disk */
                 if let Err(e) = my_blockchain.append(mined_block.clone()) {
                     println!("Append error: {e:?}");
+                } else if let Err(e) = store.add_block(my_blockchain.blocks.len() as u64 - 1, &mined_block) {
+                    println!("Persisting block failed: {e:?}");
                 }
 
                 /* Get block proposition */
@@ -239,9 +270,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
                             let new_blockchain: IshIshBlockchain = serde_json::from_str(&serialized)?;
 
                             /* Processing, consume both and return selected */
+                            let local_peer = *swarm.local_peer_id();
                             my_blockchain = process_new_blockchain(
-                                new_blockchain, 
-                                my_blockchain
+                                new_blockchain,
+                                my_blockchain,
+                                message.source,
+                                local_peer,
                             )?;
 
                             /* Get block proposition */
@@ -0,0 +1,220 @@
+/* JSON-RPC server for inspecting and driving a node from the outside (a
+ * wallet, a block explorer, an exploit script). The server itself only
+ * speaks jsonrpsee; since `Config` borrows the EVM state and can't be
+ * shared behind a lock across an async runtime boundary, each RPC method
+ * sends a `RpcRequest` down a channel and awaits the answer from the main
+ * select! loop, the same pattern already used for `DvbCommand`.
+ *
+ * Alongside the original `dvb_*` methods, a handful of standard `eth_*`
+ * methods are exposed over the same request/reply channel so off-the-shelf
+ * Ethereum tooling (wallets, scripts, exploit PoCs) can talk to the chain
+ * without knowing it's `ishishnet`. */
+
+use std::net::SocketAddr;
+
+use alloy::primitives::{Address, Bytes, U256};
+use jsonrpsee::server::{RpcModule, Server, ServerHandle};
+use jsonrpsee::types::ErrorObjectOwned;
+use revm::primitives::{ExecutionResult, TransactTo};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::config::Config;
+use crate::consensus::DvbBlock;
+use crate::data::broadcast_new_transaction;
+use crate::settlement::{get_address_balance, get_address_code, get_address_nonce, DvbTransaction};
+
+pub enum RpcRequest {
+    GetBalance(Address, oneshot::Sender<i64>),
+    GetBlockByNumber(u64, oneshot::Sender<Option<DvbBlock>>),
+    SendTransaction(DvbTransaction, oneshot::Sender<Result<(), String>>),
+    GetPoolTransactions(oneshot::Sender<Vec<DvbTransaction>>),
+    /* eth_-only requests, answered against the same `Config` */
+    BlockNumber(oneshot::Sender<u64>),
+    GetBlockByHash([u8; 32], oneshot::Sender<Option<DvbBlock>>),
+    GetTransactionCount(Address, oneshot::Sender<u64>),
+    GetCode(Address, oneshot::Sender<Bytes>),
+    Call(Address, Option<Address>, Bytes, U256, oneshot::Sender<Result<Bytes, String>>),
+}
+
+fn internal_error(msg: impl ToString) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(-32000, msg.to_string(), None::<()>)
+}
+
+/* Runs on the tokio runtime shared with the swarm/mining tasks; handed a
+ * sender so callers can push work back into `process_rpc_request`. */
+pub async fn run_rpc_server(
+    addr: SocketAddr,
+    rpc_tx: mpsc::Sender<RpcRequest>,
+) -> Result<ServerHandle, Box<dyn std::error::Error>> {
+    let server = Server::builder().build(addr).await?;
+
+    let mut module = RpcModule::new(rpc_tx);
+
+    module.register_async_method("dvb_getBalance", |params, rpc_tx, _| async move {
+        let address: Address = params.one().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::GetBalance(address, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)
+    })?;
+
+    module.register_async_method("dvb_getBlockByNumber", |params, rpc_tx, _| async move {
+        let number: u64 = params.one().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::GetBlockByNumber(number, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)
+    })?;
+
+    module.register_async_method("dvb_sendTransaction", |params, rpc_tx, _| async move {
+        let transaction: DvbTransaction = params.one().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::SendTransaction(transaction, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)?.map_err(internal_error)
+    })?;
+
+    module.register_async_method("dvb_getPoolTransactions", |_params, rpc_tx, _| async move {
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::GetPoolTransactions(tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)
+    })?;
+
+    /* eth_blockNumber: the chain's height */
+    module.register_async_method("eth_blockNumber", |_params, rpc_tx, _| async move {
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::BlockNumber(tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)
+    })?;
+
+    /* eth_getBlockByNumber(number, full): `full` is accepted for
+     * compatibility but ignored -- `DvbBlock` always serializes its
+     * transactions inline, there's no lighter "hashes only" form. */
+    module.register_async_method("eth_getBlockByNumber", |params, rpc_tx, _| async move {
+        let (number, _full): (u64, bool) = params.parse().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::GetBlockByNumber(number, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)
+    })?;
+
+    module.register_async_method("eth_getBlockByHash", |params, rpc_tx, _| async move {
+        let (hash, _full): ([u8; 32], bool) = params.parse().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::GetBlockByHash(hash, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)
+    })?;
+
+    module.register_async_method("eth_getBalance", |params, rpc_tx, _| async move {
+        let address: Address = params.one().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::GetBalance(address, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)
+    })?;
+
+    module.register_async_method("eth_getTransactionCount", |params, rpc_tx, _| async move {
+        let address: Address = params.one().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::GetTransactionCount(address, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)
+    })?;
+
+    module.register_async_method("eth_getCode", |params, rpc_tx, _| async move {
+        let address: Address = params.one().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::GetCode(address, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)
+    })?;
+
+    /* eth_sendRawTransaction: there's no RLP encoding in this chain, so
+     * "raw" is the same signed `DvbTransaction` JSON `dvb_sendTransaction`
+     * takes -- the method name is what tooling expects, not the wire format. */
+    module.register_async_method("eth_sendRawTransaction", |params, rpc_tx, _| async move {
+        let transaction: DvbTransaction = params.one().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::SendTransaction(transaction, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)?.map_err(internal_error)
+    })?;
+
+    /* eth_call: (from, to, data, value), executed against the live state
+     * without committing the result. */
+    module.register_async_method("eth_call", |params, rpc_tx, _| async move {
+        let (from, to, data, value): (Address, Option<Address>, Bytes, U256) =
+            params.parse().map_err(internal_error)?;
+        let (tx, rx) = oneshot::channel();
+        rpc_tx.send(RpcRequest::Call(from, to, data, value, tx)).await.map_err(internal_error)?;
+        rx.await.map_err(internal_error)?.map_err(internal_error)
+    })?;
+
+    let handle = server.start(module);
+    println!("JSON-RPC server listening on {addr}");
+    Ok(handle)
+}
+
+/* Called from the node's main select! loop to actually service a request
+ * against the live `Config`. */
+pub async fn process_rpc_request(
+    request: RpcRequest,
+    cfg: &mut Config<'_>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match request {
+        RpcRequest::GetBalance(address, reply) => {
+            let _ = reply.send(get_address_balance(cfg.evm.db_mut(), address));
+        },
+        RpcRequest::GetBlockByNumber(number, reply) => {
+            let block = cfg.blockchain.blocks.get(number as usize).cloned();
+            let _ = reply.send(block);
+        },
+        RpcRequest::SendTransaction(transaction, reply) => {
+            if !transaction.verify_signature() {
+                let _ = reply.send(Err("invalid signature".to_string()));
+                return Ok(());
+            }
+
+            let result = broadcast_new_transaction(&mut cfg.swarm, &cfg.topic, &transaction).await;
+            match result {
+                Ok(()) => {
+                    cfg.transactions.push(transaction);
+                    let _ = reply.send(Ok(()));
+                },
+                Err(e) => {
+                    let _ = reply.send(Err(e.to_string()));
+                }
+            }
+        },
+        RpcRequest::GetPoolTransactions(reply) => {
+            let _ = reply.send(cfg.transactions.clone());
+        },
+        RpcRequest::BlockNumber(reply) => {
+            let _ = reply.send(cfg.blockchain.blocks.len() as u64);
+        },
+        RpcRequest::GetBlockByHash(hash, reply) => {
+            let block = cfg.blockchain.blocks.iter().find(|b| b.header.cur_hash == hash).cloned();
+            let _ = reply.send(block);
+        },
+        RpcRequest::GetTransactionCount(address, reply) => {
+            let _ = reply.send(get_address_nonce(cfg.evm.db_mut(), address));
+        },
+        RpcRequest::GetCode(address, reply) => {
+            let _ = reply.send(get_address_code(cfg.evm.db_mut(), address));
+        },
+        RpcRequest::Call(from, to, data, value, reply) => {
+            /* `transact` (as opposed to `transact_commit`) never writes the
+             * result back to the `CacheDB`, so the live state is untouched
+             * even though we don't clone it up front. */
+            let transact_to = match to {
+                Some(to) => TransactTo::Call(to),
+                None => TransactTo::Create,
+            };
+            cfg.evm.tx_mut().caller = from;
+            cfg.evm.tx_mut().transact_to = transact_to;
+            cfg.evm.tx_mut().data = data;
+            cfg.evm.tx_mut().value = value;
+
+            let result = cfg.evm.transact().map(|res| res.result);
+            let response = match result {
+                Ok(ExecutionResult::Success { output, .. }) => Ok(output.into_data()),
+                Ok(other) => Err(format!("eth_call reverted: {other:?}")),
+                Err(e) => Err(format!("eth_call failed: {e:?}")),
+            };
+            let _ = reply.send(response);
+        },
+    }
+    Ok(())
+}
@@ -1,13 +1,19 @@
 
 use std::error::Error;
 use revm::db::InMemoryDB;
-use alloy::primitives::{Address, U256};
+use revm::{inspector_handle_register, Evm};
+use revm::primitives::{ExecutionResult, TransactTo};
+use alloy::primitives::{Address, Bytes, Signature, U256};
+use alloy::signers::{wallet::LocalWallet, Signer};
 use serde::{Serialize, Deserialize};
 
+use crate::common::DvbError;
 use crate::consensus::{
     DvbBlockchain,
     DvbBlock,
 };
+use crate::storage::BlockStore;
+use crate::trace::StepTracer;
 
 
 fn increase_account(
@@ -21,7 +27,7 @@ fn increase_account(
         new_acc_info.balance += U256::from(amount);
         println!("Updated balance for {}: {:?}", address, new_acc_info);
         db.insert_account_info(address, new_acc_info);
-        
+
     }
     Ok(())
 }
@@ -37,27 +43,136 @@ fn decrease_account(
         new_acc_info.balance -= U256::from(amount);
         println!("Updated balance for {}: {:?}", address, new_acc_info);
         db.insert_account_info(address, new_acc_info);
-        
+
+    }
+    Ok(())
+}
+
+fn bump_nonce(
+    db: &mut InMemoryDB,
+    address: Address,
+) -> Result<(), Box<dyn Error>> {
+    if let Ok(db_acc) = db.load_account(address)
+    {
+        let mut new_acc_info = db_acc.info.clone();
+        new_acc_info.nonce += 1;
+        db.insert_account_info(address, new_acc_info);
     }
     Ok(())
 }
 
+pub fn get_address_nonce(
+    db: &mut InMemoryDB,
+    address: Address
+) -> u64 {
+    db.load_account(address).unwrap().info.nonce
+}
+
+/* The deployed bytecode at `address`, or empty for an EOA/unknown account --
+ * used by the RPC server's `eth_getCode`. */
+pub fn get_address_code(
+    db: &mut InMemoryDB,
+    address: Address
+) -> Bytes {
+    let info = &db.load_account(address).unwrap().info;
+    info.code.clone()
+        .map(|code| Bytes::from(code.original_bytes()))
+        .unwrap_or_default()
+}
+
+/* Reject (without mutating anything) a transaction that replays a nonce or
+ * overdraws its sender, rather than letting `decrease_account` underflow.
+ * Only handles plain value transfers; a transaction carrying calldata or
+ * targeting no address (contract creation) goes through
+ * `execute_contract_transaction` instead. */
 fn process_transaction(
     db: &mut InMemoryDB,
     tx: &DvbTransaction,
-) -> Result<(), Box<dyn Error>>
+) -> Result<(), DvbError>
 {
     let from = tx.from;
-    let to = tx.to;
+    let to = tx.to.ok_or(DvbError::InvalidEvent)?;
     let amount = tx.amount;
 
-    decrease_account(db, from, amount)?;
-    increase_account(db, to, amount)?;
+    let current_nonce = get_address_nonce(db, from);
+    if tx.nonce != current_nonce {
+        return Err(DvbError::InvalidNonce);
+    }
+
+    if amount < 0 || get_address_balance(db, from) < amount {
+        return Err(DvbError::InsufficientFunds);
+    }
+
+    decrease_account(db, from, amount).map_err(|_| DvbError::ParseError)?;
+    increase_account(db, to, amount).map_err(|_| DvbError::ParseError)?;
+    bump_nonce(db, from).map_err(|_| DvbError::ParseError)?;
+
     println!("Updated balance for {}: {:?}", from, get_address_balance(db, from));
     println!("Updated balance for {}: {:?}", to, get_address_balance(db, to));
     Ok(())
 }
 
+/* A transaction is routed through the real interpreter, instead of the
+ * naive balance arithmetic above, whenever it carries calldata or has no
+ * `to` (contract creation). */
+fn is_contract_transaction(tx: &DvbTransaction) -> bool {
+    tx.to.is_none() || tx.data.as_ref().is_some_and(|data| !data.is_empty())
+}
+
+/* Run a deployment or call through revm's interpreter so it actually
+ * mutates the `CacheDB`, instead of just shuffling a balance. When
+ * `trace_execution` is set, attaches a `StepTracer` and dumps its trace
+ * to stdout once the transaction lands. */
+fn execute_contract_transaction(
+    evm: &mut Evm<'_, (), InMemoryDB>,
+    tx: &DvbTransaction,
+    trace_execution: bool,
+) -> Result<(), DvbError> {
+    let transact_to = match tx.to {
+        Some(to) => TransactTo::Call(to),
+        None => TransactTo::Create,
+    };
+    let data = tx.data.clone().unwrap_or_default();
+    let value = U256::from(tx.amount.max(0));
+
+    let result = if trace_execution {
+        let mut tracer = StepTracer::default();
+        let mut traced_evm = Evm::builder()
+            .with_db(evm.db_mut())
+            .with_external_context(&mut tracer)
+            .append_handler_register(inspector_handle_register)
+            .modify_tx_env(|tx_env| {
+                tx_env.caller = tx.from;
+                tx_env.transact_to = transact_to;
+                tx_env.data = data;
+                tx_env.value = value;
+                tx_env.nonce = Some(tx.nonce);
+            })
+            .build();
+
+        let result = traced_evm.transact_commit().map_err(|_| DvbError::InvalidEvent)?;
+        println!("Execution trace for {:?}:", tx);
+        tracer.trace.dump();
+        result
+    } else {
+        evm.tx_mut().caller = tx.from;
+        evm.tx_mut().transact_to = transact_to;
+        evm.tx_mut().data = data;
+        evm.tx_mut().value = value;
+        evm.tx_mut().nonce = Some(tx.nonce);
+
+        evm.transact_commit().map_err(|_| DvbError::InvalidEvent)?
+    };
+
+    match result {
+        ExecutionResult::Success { .. } => Ok(()),
+        other => {
+            println!("Contract transaction {:?} reverted: {:?}", tx, other);
+            Err(DvbError::InvalidEvent)
+        }
+    }
+}
+
 fn remove_transaction_from_pool(
     tx: &DvbTransaction,
     transactions: &mut Vec<DvbTransaction>,
@@ -74,28 +189,69 @@ fn remove_transaction_from_pool(
     Ok(())
 }
 
+/* Persist the balances touched by `addresses` to `store`, if one is configured */
+fn persist_balances(
+    db: &mut InMemoryDB,
+    store: Option<&BlockStore>,
+    addresses: &[Address],
+) -> Result<(), Box<dyn Error>> {
+    if let Some(store) = store {
+        for address in addresses {
+            let balance = get_address_balance(db, *address);
+            store.set_balance(*address, &balance.to_string())?;
+        }
+    }
+    Ok(())
+}
 
 pub fn progress_state(
-    db: &mut InMemoryDB, 
-    block: &DvbBlock, 
-    transactions: &mut Vec<DvbTransaction>
+    evm: &mut Evm<'_, (), InMemoryDB>,
+    block: &DvbBlock,
+    transactions: &mut Vec<DvbTransaction>,
+    store: Option<&BlockStore>,
+    trace_execution: bool,
 ) -> Result<(), Box<dyn Error>> {
     /* reward coinbase */
     let coinbase = block.header.coinbase;
 
-    increase_account(db, coinbase, 1)?;
+    increase_account(evm.db_mut(), coinbase, 1)?;
 
-    /* process transactions */
+    /* process transactions, writing only the touched balances through to storage.
+     * An invalid tx (stale nonce, overdraft, reverted call) is skipped and
+     * logged rather than aborting the whole block. */
     for tx in block.content.iter() {
-        process_transaction(db, tx)?;
+        let outcome = if tx.amount < 0 {
+            /* `process_transaction` already rejects this, but
+             * `execute_contract_transaction` silently clamps a negative
+             * amount to zero instead -- reject here first so a malformed
+             * negative-amount contract call doesn't get executed as if it
+             * were a valid zero-value call. */
+            Err(DvbError::InsufficientFunds)
+        } else if is_contract_transaction(tx) {
+            execute_contract_transaction(evm, tx, trace_execution)
+        } else {
+            process_transaction(evm.db_mut(), tx)
+        };
+
+        if let Err(e) = outcome {
+            println!("Skipping invalid transaction {:?}: {:?}", tx, e);
+            continue;
+        }
+
         remove_transaction_from_pool(tx, transactions)?;
+        match tx.to {
+            Some(to) => persist_balances(evm.db_mut(), store, &[tx.from, to])?,
+            None => persist_balances(evm.db_mut(), store, &[tx.from])?,
+        }
     }
 
+    persist_balances(evm.db_mut(), store, &[coinbase])?;
+
     Ok(())
 }
 
 pub fn get_address_balance(
-    db: &mut InMemoryDB, 
+    db: &mut InMemoryDB,
     address: Address
 ) -> i64 {
     let db_acc = db.load_account(address).unwrap();
@@ -105,14 +261,34 @@ pub fn get_address_balance(
 }
 
 pub fn refresh_state(
-    db: &mut InMemoryDB, 
-    chain: &DvbBlockchain, 
+    evm: &mut Evm<'_, (), InMemoryDB>,
+    chain: &DvbBlockchain,
     transactions: &mut Vec<DvbTransaction>
 ) -> Result<(), Box<dyn Error>> {
 
-    /* Progress the state for each block in the blockchain */
+    /* Progress the state for each block in the blockchain. This is a
+     * passive resync, not a freshly mined block, so tracing is never
+     * attached here even if the node is configured to trace live blocks. */
     for block in chain.blocks.iter() {
-        progress_state(db, block, transactions)?;
+        progress_state(evm, block, transactions, None, false)?;
+    }
+    Ok(())
+}
+
+/* Materialize balances from the persisted store instead of replaying the
+ * whole chain from genesis. Used on node startup when a `BlockStore` is
+ * configured. */
+pub fn load_state_from_store(
+    db: &mut InMemoryDB,
+    store: &BlockStore,
+) -> Result<(), Box<dyn Error>> {
+    for (address, balance) in store.load_balances()? {
+        let amount: i64 = balance.parse().map_err(|_| "invalid stored balance")?;
+        if let Ok(db_acc) = db.load_account(address) {
+            let mut new_acc_info = db_acc.info.clone();
+            new_acc_info.balance = U256::from(amount);
+            db.insert_account_info(address, new_acc_info);
+        }
     }
     Ok(())
 }
@@ -120,6 +296,48 @@ pub fn refresh_state(
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct DvbTransaction {
     pub from: Address,
-    pub to: Address,
+    /* None means contract creation */
+    pub to: Option<Address>,
     pub amount: i64,
+    pub nonce: u64,
+    /* Calldata for a contract call/creation; absent or empty for a plain transfer */
+    pub data: Option<Bytes>,
+    pub signature: Signature,
+}
+
+impl DvbTransaction {
+    /* The exact bytes the sender signs and every verifier recomputes */
+    fn signing_message(from: Address, to: Option<Address>, amount: i64, nonce: u64, data: &Option<Bytes>) -> String {
+        let to = to.map(|a| a.to_string()).unwrap_or_else(|| "create".to_string());
+        let data = data.as_ref().map(|d| d.to_string()).unwrap_or_default();
+        format!("{from}:{to}:{amount}:{nonce}:{data}")
+    }
+
+    /* Build and sign a transaction in one step, ishishnet.wallet-style */
+    pub async fn new_signed(
+        from: Address,
+        to: Option<Address>,
+        amount: i64,
+        nonce: u64,
+        data: Option<Bytes>,
+        signer: &LocalWallet,
+    ) -> Result<Self, Box<dyn Error>> {
+        let signature = signer
+            .sign_message(Self::signing_message(from, to, amount, nonce, &data).as_bytes())
+            .await?;
+
+        Ok(Self { from, to, amount, nonce, data, signature })
+    }
+
+    /* Recover the address that produced `signature` over this transaction's fields */
+    pub fn recover_signer(&self) -> Result<Address, DvbError> {
+        self.signature
+            .recover_address_from_msg(Self::signing_message(self.from, self.to, self.amount, self.nonce, &self.data).as_bytes())
+            .map_err(|_| DvbError::InvalidEvent)
+    }
+
+    /* A transaction is only authentic if the recovered signer matches `from` */
+    pub fn verify_signature(&self) -> bool {
+        matches!(self.recover_signer(), Ok(address) if address == self.from)
+    }
 }
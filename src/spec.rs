@@ -0,0 +1,91 @@
+/* JSON chain-spec loader, modeled loosely on Ethereum client chainspecs:
+ * a name, the consensus engine and its starting difficulty, a genesis
+ * block, and a set of pre-funded accounts. Lets a CTF scenario ship as a
+ * single file instead of relying on whatever `DEFAULT_DIFFICULTY` and an
+ * empty `CacheDB` happen to give you. */
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use alloy::primitives::{Address, Bytes, U256};
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{AccountInfo, Bytecode, KECCAK_EMPTY};
+use serde::Deserialize;
+
+use crate::common::DvbError;
+use crate::consensus::DvbBlock;
+use crate::engine::ConsensusKind;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EngineSpec {
+    pub kind: ConsensusKind,
+    pub difficulty: usize,
+    /* Only meaningful for `ConsensusKind::AuthorityRound`; empty/zero for
+     * every other engine, which don't have a validator set or step clock. */
+    #[serde(default)]
+    pub validators: Vec<Address>,
+    #[serde(default)]
+    pub step_duration: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GenesisSpec {
+    pub coinbase: Address,
+    pub timestamp: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AccountSpec {
+    pub balance: U256,
+    #[serde(default)]
+    pub nonce: u64,
+    #[serde(default)]
+    pub code: Option<Bytes>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChainSpec {
+    pub name: String,
+    pub engine: EngineSpec,
+    pub genesis: GenesisSpec,
+    #[serde(default)]
+    pub accounts: HashMap<Address, AccountSpec>,
+}
+
+impl ChainSpec {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, DvbError> {
+        let data = fs::read_to_string(path).map_err(|_| DvbError::ParseError)?;
+        let spec: Self = serde_json::from_str(&data)?;
+        Ok(spec)
+    }
+
+    /* The `CacheDB` the EVM starts from, pre-funded with every account in `accounts` */
+    pub fn build_state(&self) -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+
+        for (address, account) in self.accounts.iter() {
+            let code = account.code.clone().map(|code| Bytecode::new_raw(code.0.into()));
+            let code_hash = code.as_ref().map(|c| c.hash_slow()).unwrap_or(KECCAK_EMPTY);
+
+            let info = AccountInfo {
+                balance: account.balance,
+                nonce: account.nonce,
+                code_hash,
+                code,
+            };
+            db.insert_account_info(*address, info);
+        }
+
+        db
+    }
+
+    /* The genesis block implied by this spec, with no transactions */
+    pub fn genesis_block(&self) -> DvbBlock {
+        DvbBlock::genesis_with_timestamp(
+            self.genesis.coinbase,
+            self.engine.difficulty,
+            self.genesis.timestamp,
+        )
+    }
+}
@@ -0,0 +1,170 @@
+/* SQLite-backed persistence for the blockchain and account balances.
+ * Mirrors the Alfis approach: init_db() creates the schema, add_block()
+ * re-runs `verify_block` on a block before inserting it. */
+
+use std::path::Path;
+
+use alloy::primitives::Address;
+use rusqlite::{params, Connection};
+
+use crate::common::DvbError;
+use crate::consensus::{DvbBlock, DvbBlockchain};
+use crate::engine::{AuthorityRoundEngine, BlockSource, ConsensusKind};
+
+pub struct BlockStore {
+    conn: Connection,
+}
+
+impl BlockStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DvbError> {
+        let conn = Connection::open(path).map_err(|_| DvbError::ParseError)?;
+        let store = Self { conn };
+        store.init_db()?;
+        Ok(store)
+    }
+
+    fn init_db(&self) -> Result<(), DvbError> {
+        self.conn
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS blocks (
+                    height      INTEGER PRIMARY KEY,
+                    nonce       INTEGER NOT NULL,
+                    difficulty  INTEGER NOT NULL,
+                    coinbase    BLOB NOT NULL,
+                    cur_hash    BLOB NOT NULL UNIQUE,
+                    prev_hash   BLOB NOT NULL,
+                    timestamp   INTEGER NOT NULL,
+                    content     TEXT NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS balances (
+                    address     BLOB PRIMARY KEY,
+                    balance     TEXT NOT NULL
+                );",
+            )
+            .map_err(|_| DvbError::ParseError)?;
+        Ok(())
+    }
+
+    /* Re-runs `verify_block` on `block` against `chain` before inserting it.
+     * It does not check that `block` links to the tip: a block whose parent
+     * isn't in `chain` is verified like a genesis block. */
+    pub fn add_block(&self, chain: &DvbBlockchain, block: &DvbBlock, engine: ConsensusKind, authority_round: Option<&AuthorityRoundEngine>) -> Result<(), DvbError> {
+        chain.verify_block(block.clone(), engine, BlockSource::Network, authority_round)?;
+
+        let content = serde_json::to_string(&block.content)?;
+
+        self.conn
+            .execute(
+                "INSERT INTO blocks (height, nonce, difficulty, coinbase, cur_hash, prev_hash, timestamp, content)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    block.header.number,
+                    block.header.nonce,
+                    block.header.difficulty as i64,
+                    block.header.coinbase.as_slice(),
+                    block.header.cur_hash.as_slice(),
+                    block.prev_hash_bytes().as_slice(),
+                    block.header.timestamp,
+                    content,
+                ],
+            )
+            .map_err(|_| DvbError::ParseError)?;
+        Ok(())
+    }
+
+    /* Replace the persisted chain wholesale, used when fork choice swaps in a
+     * heavier remote chain: the pre-reorg tip is no longer part of the
+     * canonical chain, so leaving it in `blocks` would make a restarted node
+     * reload the stale chain instead of the one its peers already believe it
+     * has. `blocks` is assumed already verified (by `process_new_blockchain`),
+     * so it's written through without re-checking each seal. */
+    pub fn replace_blocks(&self, blocks: &[DvbBlock]) -> Result<(), DvbError> {
+        self.conn.execute("DELETE FROM blocks", []).map_err(|_| DvbError::ParseError)?;
+
+        for block in blocks {
+            let content = serde_json::to_string(&block.content)?;
+            self.conn
+                .execute(
+                    "INSERT INTO blocks (height, nonce, difficulty, coinbase, cur_hash, prev_hash, timestamp, content)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        block.header.number,
+                        block.header.nonce,
+                        block.header.difficulty as i64,
+                        block.header.coinbase.as_slice(),
+                        block.header.cur_hash.as_slice(),
+                        block.prev_hash_bytes().as_slice(),
+                        block.header.timestamp,
+                        content,
+                    ],
+                )
+                .map_err(|_| DvbError::ParseError)?;
+        }
+        Ok(())
+    }
+
+    pub fn load_blocks(&self) -> Result<Vec<DvbBlock>, DvbError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT height, nonce, difficulty, coinbase, cur_hash, prev_hash, timestamp, content FROM blocks ORDER BY height ASC")
+            .map_err(|_| DvbError::ParseError)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let height: u64 = row.get(0)?;
+                let nonce: u64 = row.get(1)?;
+                let difficulty: i64 = row.get(2)?;
+                let coinbase: Vec<u8> = row.get(3)?;
+                let cur_hash: Vec<u8> = row.get(4)?;
+                let prev_hash: Vec<u8> = row.get(5)?;
+                let timestamp: u64 = row.get(6)?;
+                let content: String = row.get(7)?;
+                Ok((height, nonce, difficulty, coinbase, cur_hash, prev_hash, timestamp, content))
+            })
+            .map_err(|_| DvbError::ParseError)?;
+
+        let mut blocks = Vec::new();
+        for row in rows {
+            let (height, nonce, difficulty, coinbase, cur_hash, prev_hash, timestamp, content) =
+                row.map_err(|_| DvbError::ParseError)?;
+            let content = serde_json::from_str(&content)?;
+            blocks.push(DvbBlock::from_stored(
+                height, nonce, difficulty as usize, &coinbase, &cur_hash, &prev_hash, timestamp, content,
+            )?);
+        }
+        Ok(blocks)
+    }
+
+    pub fn set_balance(&self, address: Address, balance: &str) -> Result<(), DvbError> {
+        self.conn
+            .execute(
+                "INSERT INTO balances (address, balance) VALUES (?1, ?2)
+                 ON CONFLICT(address) DO UPDATE SET balance = excluded.balance",
+                params![address.as_slice(), balance],
+            )
+            .map_err(|_| DvbError::ParseError)?;
+        Ok(())
+    }
+
+    pub fn load_balances(&self) -> Result<Vec<(Address, String)>, DvbError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT address, balance FROM balances")
+            .map_err(|_| DvbError::ParseError)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let address: Vec<u8> = row.get(0)?;
+                let balance: String = row.get(1)?;
+                Ok((address, balance))
+            })
+            .map_err(|_| DvbError::ParseError)?;
+
+        let mut balances = Vec::new();
+        for row in rows {
+            let (address, balance) = row.map_err(|_| DvbError::ParseError)?;
+            balances.push((Address::from_slice(&address), balance));
+        }
+        Ok(balances)
+    }
+}
@@ -0,0 +1,49 @@
+/* An optional revm `Inspector` that records every interpreter step for a
+ * single transaction: program counter, opcode, remaining gas, and stack
+ * depth. Meant for debugging a deployment/call and for teaching how a
+ * vulnerable contract gets exploited, not for production use -- it's only
+ * ever attached when `Config::trace_execution` is set. */
+
+use revm::Database;
+use revm::interpreter::Interpreter;
+use revm::{EvmContext, Inspector};
+
+#[derive(Debug, Clone)]
+pub struct TraceStep {
+    pub pc: usize,
+    pub opcode: u8,
+    pub gas_remaining: u64,
+    pub stack_depth: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionTrace {
+    pub steps: Vec<TraceStep>,
+}
+
+impl ExecutionTrace {
+    pub fn dump(&self) {
+        for step in self.steps.iter() {
+            println!(
+                "  pc={} op=0x{:02x} gas_left={} stack_depth={}",
+                step.pc, step.opcode, step.gas_remaining, step.stack_depth
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StepTracer {
+    pub trace: ExecutionTrace,
+}
+
+impl<DB: Database> Inspector<DB> for StepTracer {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        self.trace.steps.push(TraceStep {
+            pc: interp.program_counter(),
+            opcode: interp.current_opcode(),
+            gas_remaining: interp.gas.remaining(),
+            stack_depth: interp.stack.len(),
+        });
+    }
+}